@@ -0,0 +1,197 @@
+//! cpufreq min/max frequency caps and turbo/boost control.
+//!
+//! EPP only biases the governor; this module handles the hard frequency
+//! caps (`scaling_min_freq`/`scaling_max_freq`, expressed as a percentage of
+//! the hardware-reported `cpuinfo_min_freq`..`cpuinfo_max_freq` range, the
+//! same way `intel_pstate`'s min/max perf pct works) and the global
+//! turbo/boost toggle.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A single CPU's cpufreq directory plus its hardware frequency bounds.
+struct CpuFreqEntry {
+    cpu_num: u32,
+    cpufreq_dir: PathBuf,
+    hw_min_khz: u64,
+    hw_max_khz: u64,
+}
+
+/// Manages cpufreq min/max caps and boost across detected CPUs.
+pub struct CpuFreqMgr {
+    entries: Vec<CpuFreqEntry>,
+}
+
+impl CpuFreqMgr {
+    /// Discovers every cpuN/cpufreq directory and its hardware frequency
+    /// bounds, the same way `EppMgr::new` discovers EPP paths.
+    pub fn new() -> Result<Self, io::Error> {
+        let cpu_dir = PathBuf::from("/sys/devices/system/cpu/");
+        let mut entries = Vec::new();
+
+        for entry in fs::read_dir(&cpu_dir)? {
+            let path = entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let Some(cpu_num) = path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.strip_prefix("cpu"))
+                .and_then(|s| s.parse::<u32>().ok())
+            else {
+                continue;
+            };
+
+            let cpufreq_dir = path.join("cpufreq");
+            if !cpufreq_dir.is_dir() {
+                continue;
+            }
+
+            let hw_min_khz = read_u64(&cpufreq_dir.join("cpuinfo_min_freq"))?;
+            let hw_max_khz = read_u64(&cpufreq_dir.join("cpuinfo_max_freq"))?;
+
+            entries.push(CpuFreqEntry {
+                cpu_num,
+                cpufreq_dir,
+                hw_min_khz,
+                hw_max_khz,
+            });
+        }
+
+        entries.sort_by_key(|e| e.cpu_num);
+
+        if entries.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "Error: No cpufreq directories found.",
+            ));
+        }
+
+        Ok(Self { entries })
+    }
+
+    fn selected(&self, cpus: &[u32]) -> Result<Vec<&CpuFreqEntry>, Box<dyn std::error::Error>> {
+        if cpus.is_empty() {
+            return Ok(self.entries.iter().collect());
+        }
+
+        let mut selected = Vec::with_capacity(cpus.len());
+        for &cpu in cpus {
+            let entry = self
+                .entries
+                .iter()
+                .find(|e| e.cpu_num == cpu)
+                .ok_or_else(|| format!("Error: CPU{} has no cpufreq directory.", cpu))?;
+            selected.push(entry);
+        }
+        Ok(selected)
+    }
+
+    /// Caps `scaling_max_freq` to `pct`% of each CPU's hardware frequency range.
+    pub fn set_max_freq_pct(
+        &self,
+        pct: u8,
+        cpus: &[u32],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for entry in self.selected(cpus)? {
+            let target_khz = scale_pct(entry.hw_min_khz, entry.hw_max_khz, pct);
+            write_u64(&entry.cpufreq_dir.join("scaling_max_freq"), target_khz)?;
+            println!(
+                "CPU{:02}: scaling_max_freq set to {} kHz ({}% of range)",
+                entry.cpu_num, target_khz, pct
+            );
+        }
+        Ok(())
+    }
+
+    /// Floors `scaling_min_freq` to `pct`% of each CPU's hardware frequency range.
+    pub fn set_min_freq_pct(
+        &self,
+        pct: u8,
+        cpus: &[u32],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for entry in self.selected(cpus)? {
+            let target_khz = scale_pct(entry.hw_min_khz, entry.hw_max_khz, pct);
+            write_u64(&entry.cpufreq_dir.join("scaling_min_freq"), target_khz)?;
+            println!(
+                "CPU{:02}: scaling_min_freq set to {} kHz ({}% of range)",
+                entry.cpu_num, target_khz, pct
+            );
+        }
+        Ok(())
+    }
+
+    /// Prints the current min/max frequency and boost status for the
+    /// selected CPUs.
+    pub fn print_status(&self, cpus: &[u32]) -> Result<(), Box<dyn std::error::Error>> {
+        for entry in self.selected(cpus)? {
+            let cur_min = read_u64(&entry.cpufreq_dir.join("scaling_min_freq"))?;
+            let cur_max = read_u64(&entry.cpufreq_dir.join("scaling_max_freq"))?;
+            println!(
+                "CPU{:02}: {} - {} kHz (hardware range {} - {} kHz)",
+                entry.cpu_num, cur_min, cur_max, entry.hw_min_khz, entry.hw_max_khz
+            );
+        }
+
+        match Self::read_boost()? {
+            Some(enabled) => println!("Boost: {}", if enabled { "enabled" } else { "disabled" }),
+            None => println!("Boost: not supported on this platform"),
+        }
+
+        Ok(())
+    }
+
+    /// Toggles turbo/boost via the vendor-appropriate sysfs knob: AMD's
+    /// `cpufreq/boost` (1=enabled) or Intel's `intel_pstate/no_turbo`
+    /// (1=disabled, inverted).
+    pub fn set_boost(enabled: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let amd_path = PathBuf::from("/sys/devices/system/cpu/cpufreq/boost");
+        let intel_path = PathBuf::from("/sys/devices/system/cpu/intel_pstate/no_turbo");
+
+        if amd_path.exists() {
+            write_u64(&amd_path, enabled as u64)?;
+        } else if intel_path.exists() {
+            write_u64(&intel_path, (!enabled) as u64)?;
+        } else {
+            return Err("Error: no boost control found (neither cpufreq/boost nor intel_pstate/no_turbo exists).".into());
+        }
+
+        println!("Boost {}", if enabled { "enabled" } else { "disabled" });
+        Ok(())
+    }
+
+    fn read_boost() -> Result<Option<bool>, Box<dyn std::error::Error>> {
+        let amd_path = PathBuf::from("/sys/devices/system/cpu/cpufreq/boost");
+        let intel_path = PathBuf::from("/sys/devices/system/cpu/intel_pstate/no_turbo");
+
+        if amd_path.exists() {
+            Ok(Some(read_u64(&amd_path)? == 1))
+        } else if intel_path.exists() {
+            Ok(Some(read_u64(&intel_path)? == 0))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Scales a percentage (0-100) onto a [min, max] kHz range.
+fn scale_pct(min_khz: u64, max_khz: u64, pct: u8) -> u64 {
+    let pct = pct.min(100) as u64;
+    min_khz + (max_khz - min_khz) * pct / 100
+}
+
+fn read_u64(path: &Path) -> Result<u64, io::Error> {
+    let contents = fs::read_to_string(path)?;
+    contents.trim().parse().map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {}", path.display(), e),
+        )
+    })
+}
+
+fn write_u64(path: &Path, value: u64) -> Result<(), io::Error> {
+    fs::write(path, format!("{}\n", value))
+}
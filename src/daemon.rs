@@ -0,0 +1,185 @@
+//! AC/battery auto-switching daemon.
+//!
+//! Watches `/sys/class/power_supply/*/online` for the Mains (AC) supply and
+//! re-applies a configured EPP profile (and optional frequency cap) whenever
+//! the power source changes, removing the need to wire up systemd/udev glue
+//! to run `eppcli` on power-source events.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{CpuFreqMgr, EppMgr};
+
+/// Default location for the daemon config file.
+const DEFAULT_CONFIG_PATH: &str = "/etc/eppcli/daemon.conf";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PowerState {
+    Ac,
+    Battery,
+}
+
+/// Daemon configuration: which profile (and optional frequency cap) to
+/// apply per power state, and how often to poll for changes.
+struct DaemonConfig {
+    ac_profile: String,
+    battery_profile: String,
+    ac_max_freq_pct: Option<u8>,
+    battery_max_freq_pct: Option<u8>,
+    poll_interval_secs: u64,
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        Self {
+            ac_profile: "performance".to_string(),
+            battery_profile: "balance_power".to_string(),
+            ac_max_freq_pct: None,
+            battery_max_freq_pct: None,
+            poll_interval_secs: 5,
+        }
+    }
+}
+
+impl DaemonConfig {
+    /// Loads `key=value` pairs from `path`, falling back to defaults for any
+    /// key that's missing, or entirely if `path` doesn't exist. Unknown keys
+    /// are warned about and ignored.
+    fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut config = DaemonConfig::default();
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(config),
+            Err(e) => {
+                return Err(format!(
+                    "Error: could not read config file {}: {}",
+                    path.display(),
+                    e
+                )
+                .into())
+            }
+        };
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!(
+                    "Error: invalid config line (expected key=value): '{}'",
+                    line
+                )
+                .into());
+            };
+            let (key, value) = (key.trim(), value.trim());
+
+            match key {
+                "ac_profile" => config.ac_profile = value.to_string(),
+                "battery_profile" => config.battery_profile = value.to_string(),
+                "ac_max_freq_pct" => config.ac_max_freq_pct = Some(value.parse()?),
+                "battery_max_freq_pct" => config.battery_max_freq_pct = Some(value.parse()?),
+                "poll_interval_secs" => config.poll_interval_secs = value.parse()?,
+                _ => eprintln!("Warning: unknown config key '{}', ignoring.", key),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Detects the current power state by scanning `/sys/class/power_supply/`
+/// for a `Mains` (AC) supply and checking whether it's online. Systems with
+/// no Mains supply at all (desktops) are treated as always-AC.
+fn detect_power_state() -> Result<PowerState, Box<dyn std::error::Error>> {
+    let power_supply_dir = PathBuf::from("/sys/class/power_supply/");
+    let mut found_mains = false;
+
+    for entry in fs::read_dir(&power_supply_dir)? {
+        let path = entry?.path();
+        let supply_type = fs::read_to_string(path.join("type")).unwrap_or_default();
+        if supply_type.trim() != "Mains" {
+            continue;
+        }
+        found_mains = true;
+
+        let online = fs::read_to_string(path.join("online")).unwrap_or_default();
+        if online.trim() == "1" {
+            return Ok(PowerState::Ac);
+        }
+    }
+
+    if found_mains {
+        Ok(PowerState::Battery)
+    } else {
+        Ok(PowerState::Ac)
+    }
+}
+
+/// Applies the configured profile (and optional frequency cap) for `state`
+/// to every detected CPU.
+fn apply_for_state(
+    epp_mgr: &EppMgr,
+    config: &DaemonConfig,
+    state: PowerState,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (profile, max_freq_pct) = match state {
+        PowerState::Ac => (&config.ac_profile, config.ac_max_freq_pct),
+        PowerState::Battery => (&config.battery_profile, config.battery_max_freq_pct),
+    };
+
+    epp_mgr.apply_profile(profile, &[])?;
+    if let Some(pct) = max_freq_pct {
+        CpuFreqMgr::new()?.set_max_freq_pct(pct, &[])?;
+    }
+
+    Ok(())
+}
+
+fn log_transition(state: PowerState, profile: &str) {
+    let epoch_secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let state_name = match state {
+        PowerState::Ac => "AC",
+        PowerState::Battery => "battery",
+    };
+    println!(
+        "[{}] Power state changed to {}, applying profile '{}'",
+        epoch_secs, state_name, profile
+    );
+}
+
+/// Runs the daemon: polls the power state and re-applies the configured
+/// profile whenever it differs from the last applied state, including a
+/// change that happened while suspended. A resume on the same power source
+/// does not re-apply the profile.
+pub fn run(config_path: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let config_path = config_path.unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_PATH));
+    let config = DaemonConfig::load(&config_path)?;
+    let epp_mgr = EppMgr::new()?;
+
+    println!(
+        "eppcli daemon started, watching power supply state every {}s.",
+        config.poll_interval_secs
+    );
+
+    let mut last_state: Option<PowerState> = None;
+    loop {
+        let state = detect_power_state()?;
+        if last_state != Some(state) {
+            apply_for_state(&epp_mgr, &config, state)?;
+            let profile = match state {
+                PowerState::Ac => &config.ac_profile,
+                PowerState::Battery => &config.battery_profile,
+            };
+            log_transition(state, profile);
+            last_state = Some(state);
+        }
+        thread::sleep(Duration::from_secs(config.poll_interval_secs));
+    }
+}
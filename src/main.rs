@@ -1,22 +1,105 @@
 use std::fs;
 use std::io::{self, Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 // Bring in clap macros and types, including CommandFactory and ArgGroup
 use clap::{ArgGroup, CommandFactory, FromArgMatches, Parser, ValueEnum};
 
-/// Manages AMD Energy Performance Preference (EPP) settings.
-struct AmdEppMgr {
+mod cpufreq;
+mod daemon;
+mod rapl;
+use cpufreq::CpuFreqMgr;
+use rapl::PowerMonitor;
+
+/// CPU vendor as reported by the `CPUID` leaf 0 string, used to corroborate
+/// the scaling driver detected from sysfs and to pick the RAPL MSR addresses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CpuVendor {
+    Amd,
+    Intel,
+    Other,
+}
+
+impl CpuVendor {
+    /// Reads the CPUID vendor string (`EBX:EDX:ECX` of leaf 0) on x86/x86_64.
+    /// Returns `Other` on any architecture where CPUID isn't available; the
+    /// vendor corroborates the sysfs driver but never gates it.
+    #[cfg(target_arch = "x86_64")]
+    fn detect() -> Self {
+        use std::arch::x86_64::__cpuid;
+
+        let regs = __cpuid(0);
+        let mut vendor = [0u8; 12];
+        vendor[0..4].copy_from_slice(&regs.ebx.to_le_bytes());
+        vendor[4..8].copy_from_slice(&regs.edx.to_le_bytes());
+        vendor[8..12].copy_from_slice(&regs.ecx.to_le_bytes());
+
+        match &vendor {
+            b"AuthenticAMD" => CpuVendor::Amd,
+            b"GenuineIntel" => CpuVendor::Intel,
+            _ => CpuVendor::Other,
+        }
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    fn detect() -> Self {
+        CpuVendor::Other
+    }
+}
+
+/// Manages Energy Performance Preference (EPP) settings across CPU cores.
+///
+/// EPP is an ACPI CPPC-defined register, not an AMD-specific feature: both
+/// `amd-pstate-epp` and `intel_pstate` (in active mode) expose the same
+/// `energy_performance_preference` sysfs file, so this manager works for
+/// either vendor as long as the active scaling driver supports it.
+struct EppMgr {
     epp_paths: Vec<PathBuf>,
+    scaling_driver: String,
+    /// The kernel's advertised `energy_performance_available_preferences`,
+    /// parsed once at startup. `None` if the platform doesn't expose the
+    /// file, in which case requested preferences aren't pre-validated.
+    available_preferences: Option<Vec<String>>,
 }
 
-impl AmdEppMgr {
-    /// Initializes the manager, find all CPU EPP paths from sysfs.
-    /// Attempt to read the /sys/devices/system/cpu/cpu*/cpufreq/energy_performance_preference
+/// Scaling drivers known to expose `energy_performance_preference`.
+const EPP_CAPABLE_DRIVERS: &[&str] = &["amd-pstate-epp", "intel_pstate", "intel_cpufreq"];
+
+impl EppMgr {
+    /// Initializes the manager: detects the active scaling driver, refuses
+    /// to proceed if it isn't EPP-capable, and finds all CPU EPP paths from
+    /// /sys/devices/system/cpu/cpu*/cpufreq/energy_performance_preference.
     fn new() -> Result<Self, io::Error> {
-        let mut epp_paths = Vec::new();
         let cpu_dir = PathBuf::from("/sys/devices/system/cpu/");
 
+        let scaling_driver = Self::read_scaling_driver(&cpu_dir)?;
+        if !EPP_CAPABLE_DRIVERS.contains(&scaling_driver.as_str()) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "Error: scaling driver '{}' does not expose energy_performance_preference.\n\
+                     EPP requires an active-mode CPPC driver (amd-pstate-epp, intel_pstate, intel_cpufreq),\n\
+                     but this system is using a passive driver such as acpi-cpufreq or amd-pstate.",
+                    scaling_driver
+                ),
+            ));
+        }
+
+        // CPUID vendor is advisory only: a mismatch against the driver name
+        // doesn't stop us, since virtualized/emulated CPUs can lie about it.
+        match (scaling_driver.as_str(), CpuVendor::detect()) {
+            ("amd-pstate-epp", CpuVendor::Intel)
+            | ("intel_pstate" | "intel_cpufreq", CpuVendor::Amd) => {
+                eprintln!(
+                    "Warning: scaling driver '{}' looks inconsistent with the detected CPU vendor.",
+                    scaling_driver
+                );
+            }
+            _ => {}
+        }
+
+        let mut epp_paths = Vec::new();
         for entry in fs::read_dir(&cpu_dir)? {
             let entry = entry?;
             let path = entry.path();
@@ -44,19 +127,101 @@ impl AmdEppMgr {
             ));
         }
 
-        Ok(Self { epp_paths })
+        let available_preferences = Self::read_available_preferences(&cpu_dir);
+
+        Ok(Self {
+            epp_paths,
+            scaling_driver,
+            available_preferences,
+        })
     }
 
-    /// Applies the specified EPP profile key to all detected CPU EPP files.
+    /// Reads `scaling_driver` from the first available cpuN/cpufreq directory.
+    /// All cores share the same driver, so cpu0 is representative.
+    fn read_scaling_driver(cpu_dir: &Path) -> Result<String, io::Error> {
+        let driver_path = cpu_dir.join("cpu0/cpufreq/scaling_driver");
+        let mut driver = String::new();
+        fs::File::open(&driver_path)?.read_to_string(&mut driver)?;
+        Ok(driver.trim().to_string())
+    }
+
+    /// Reads `energy_performance_available_preferences` from cpu0, mirroring
+    /// how CPPC exposes queryable EPP capabilities rather than assuming the
+    /// fixed four-value set `EppValue` covers every platform (some add
+    /// `default`, others omit `power`). Returns `None` if the file isn't
+    /// present, since older kernels don't expose it.
+    fn read_available_preferences(cpu_dir: &Path) -> Option<Vec<String>> {
+        let path = cpu_dir.join("cpu0/cpufreq/energy_performance_available_preferences");
+        let contents = fs::read_to_string(path).ok()?;
+        Some(contents.split_whitespace().map(String::from).collect())
+    }
+
+    /// Extracts the numeric CPU index from an EPP path's grandparent
+    /// directory name, e.g. `.../cpu7/cpufreq/energy_performance_preference` -> `7`.
+    fn cpu_index(path: &Path) -> Option<u32> {
+        path.parent()
+            .and_then(|p| p.parent())
+            .and_then(|p| p.file_name())
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.strip_prefix("cpu"))
+            .and_then(|s| s.parse().ok())
+    }
+
+    /// Resolves the EPP paths to operate on, optionally restricted to a list
+    /// of CPU indices. An empty `cpus` selects every detected CPU. Errors if
+    /// a requested index has no matching EPP file.
+    fn select_paths(&self, cpus: &[u32]) -> Result<Vec<&PathBuf>, Box<dyn std::error::Error>> {
+        if cpus.is_empty() {
+            return Ok(self.epp_paths.iter().collect());
+        }
+
+        let mut selected = Vec::with_capacity(cpus.len());
+        for &cpu in cpus {
+            let path = self
+                .epp_paths
+                .iter()
+                .find(|p| Self::cpu_index(p) == Some(cpu))
+                .ok_or_else(|| {
+                    format!(
+                        "Error: CPU{} has no energy_performance_preference file.",
+                        cpu
+                    )
+                })?;
+            selected.push(path);
+        }
+        Ok(selected)
+    }
+
+    /// Applies the specified EPP profile key to the selected CPU EPP files.
     /// This requires the application itself to be run with root permissions.
-    fn apply_profile(&self, profile_key: &str) -> Result<(), Box<dyn std::error::Error>> {
+    fn apply_profile(
+        &self,
+        profile_key: &str,
+        cpus: &[u32],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Raw register values (0-255) bypass the named-preference check: the
+        // kernel accepts any value in range regardless of the advertised set.
+        if profile_key.parse::<u8>().is_err() {
+            if let Some(available) = &self.available_preferences {
+                if !available.iter().any(|p| p == profile_key) {
+                    return Err(format!(
+                        "Error: '{}' is not an available EPP preference on this system.\n\
+                         Available preferences: {}",
+                        profile_key,
+                        available.join(", ")
+                    )
+                    .into());
+                }
+            }
+        }
+
         println!("Applying EPP setting: {}", profile_key);
 
         // Append a newline to the profile key since the kernel expects it that way.
         // This is consistent with the behavior of writing to /sys files using shell command.
         let sys_profile = format!("{}\n", profile_key);
 
-        for path in &self.epp_paths {
+        for path in self.select_paths(cpus)? {
             let mut file = match fs::OpenOptions::new().write(true).open(path) {
                 Ok(file) => file,
                 Err(ref e) if e.kind() == io::ErrorKind::PermissionDenied => {
@@ -93,26 +258,16 @@ impl AmdEppMgr {
         Ok(())
     }
 
-    /// Reads the current EPP value for all CPUs and prints them.
-    fn read_profile(&self) -> Result<(), io::Error> {
+    /// Reads the current EPP value for the selected CPUs and prints them.
+    fn read_profile(&self, cpus: &[u32]) -> Result<(), Box<dyn std::error::Error>> {
+        println!("Scaling driver: {}\n", self.scaling_driver);
+
         // CPU_Label : EPP_Value
         let mut cpu_data: Vec<(String, String)> = Vec::new();
 
-        for path in &self.epp_paths {
-            let cpu_num_str = path
-                .parent()
-                .and_then(|p| p.parent())
-                .and_then(|p| p.file_name())
-                .and_then(|s| s.to_str())
-                .and_then(|s| s.strip_prefix("cpu"));
-
-            let cpu_num: u32 = match cpu_num_str {
-                Some(num_str) => num_str.parse().map_err(|e| {
-                    io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        format!("Invalid CPU number in path: {}", e),
-                    )
-                })?,
+        for path in self.select_paths(cpus)? {
+            let cpu_num = match Self::cpu_index(path) {
+                Some(num) => num,
                 None => {
                     eprintln!(
                         "Warning: Could not extract CPU number from path: {:?}",
@@ -124,9 +279,16 @@ impl AmdEppMgr {
 
             let mut epp_value = String::new();
             fs::File::open(path)?.read_to_string(&mut epp_value)?;
-            let epp_value = epp_value.trim().to_string(); // Trim and convert to String
+            let epp_value = epp_value.trim();
 
-            cpu_data.push((format!("CPU{:02}", cpu_num), epp_value));
+            // Raw register values don't carry a name, so annotate them with
+            // the nearest named band for context.
+            let display_value = match epp_value.parse::<u8>() {
+                Ok(raw) => format!("{} (~{})", raw, EppValue::nearest_band(raw).as_str()),
+                Err(_) => epp_value.to_string(),
+            };
+
+            cpu_data.push((format!("CPU{:02}", cpu_num), display_value));
         }
 
         cpu_data.sort_by(|a, b| a.0.cmp(&b.0)); // Sort by CPU label
@@ -211,6 +373,65 @@ impl EppValue {
             _ => None,
         }
     }
+
+    /// Maps a raw CPPC EPP register value (0=max performance, 255=max power
+    /// saving) to the nearest named band, by splitting the 0-255 range into
+    /// even quarters.
+    fn nearest_band(raw: u8) -> Self {
+        match raw {
+            0..=63 => EppValue::Performance,
+            64..=127 => EppValue::BalancePerformance,
+            128..=191 => EppValue::BalancePower,
+            192..=255 => EppValue::Power,
+        }
+    }
+}
+
+/// A parsed `--cpu` selector. Wraps `Vec<u32>` in a newtype so
+/// `clap_derive` treats `--cpu` as a single occurrence holding one `CpuList`
+/// (via `remove_one`) rather than mistaking it, by its literal `Vec<u32>`
+/// field shape, for one `u32` per occurrence (via `remove_many`).
+#[derive(Debug, Clone)]
+struct CpuList(Vec<u32>);
+
+/// Parses a `cpupower`-style CPU selector such as `0-3,8,10-11` into a sorted,
+/// deduplicated list of CPU indices.
+fn parse_cpu_list(s: &str) -> Result<CpuList, String> {
+    let mut cpus = Vec::new();
+
+    for part in s.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+
+        if let Some((start, end)) = part.split_once('-') {
+            let start: u32 = start
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid CPU range: '{}'", part))?;
+            let end: u32 = end
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid CPU range: '{}'", part))?;
+            if start > end {
+                return Err(format!(
+                    "Invalid CPU range: '{}' (start must not exceed end)",
+                    part
+                ));
+            }
+            cpus.extend(start..=end);
+        } else {
+            let cpu: u32 = part
+                .parse()
+                .map_err(|_| format!("Invalid CPU index: '{}'", part))?;
+            cpus.push(cpu);
+        }
+    }
+
+    cpus.sort_unstable();
+    cpus.dedup();
+    Ok(CpuList(cpus))
 }
 
 /// Helper function to generate the custom help section for EPP profiles.
@@ -235,7 +456,7 @@ fn get_profile_help_section() -> String {
 }
 
 #[derive(Parser, Debug)]
-#[command(author, version, about = "Manage AMD Energy Performance Preference (EPP) settings.", long_about = None)]
+#[command(author, version, about = "Manage CPU Energy Performance Preference (EPP) settings.", long_about = None)]
 #[command(help_template = "{usage}\n\n{about}\n\n{options}\n{after-help}")]
 #[clap(group(ArgGroup::new("epp_action").multiple(false)))]
 struct Cli {
@@ -271,6 +492,16 @@ struct Cli {
     )]
     profile_level: Option<u8>,
 
+    // Raw CPPC EPP register value, for finer control than the four presets
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Set EPP to a raw register value (0-255).\n\
+        0=max performance, 255=max power saving.",
+        group = "epp_action"
+    )]
+    raw: Option<u8>,
+
     // Show current profile (mutually exclusive with setting profiles)
     #[arg(
         long,
@@ -279,6 +510,71 @@ struct Cli {
         group = "epp_action"
     )]
     show: bool,
+
+    #[arg(
+        long,
+        value_name = "LIST",
+        value_parser = parse_cpu_list,
+        help = "Restrict the action to specific CPUs, e.g. '0-3,8,10-11'.\n\
+        Defaults to all detected CPUs."
+    )]
+    cpu: Option<CpuList>,
+
+    // Monitor mode (mutually exclusive with setting/showing profiles)
+    #[arg(
+        long,
+        help = "Show live package power draw (watts) via RAPL.",
+        group = "epp_action"
+    )]
+    monitor: bool,
+
+    #[arg(
+        long,
+        value_name = "SECS",
+        requires = "monitor",
+        default_value_t = 1,
+        help = "Sampling interval in seconds for --monitor."
+    )]
+    interval: u64,
+
+    // cpufreq min/max caps and turbo/boost (orthogonal to the EPP action above,
+    // so these can be combined with e.g. --balance-performance in one invocation)
+    #[arg(
+        long,
+        value_name = "PCT",
+        help = "Cap scaling_max_freq to PCT% of the hardware frequency range."
+    )]
+    max_freq_pct: Option<u8>,
+
+    #[arg(
+        long,
+        value_name = "PCT",
+        help = "Floor scaling_min_freq to PCT% of the hardware frequency range."
+    )]
+    min_freq_pct: Option<u8>,
+
+    #[arg(long, help = "Enable CPU turbo/boost.", conflicts_with = "no_boost")]
+    boost: bool,
+
+    #[arg(long, help = "Disable CPU turbo/boost.")]
+    no_boost: bool,
+
+    // Daemon mode (mutually exclusive with the EPP, show and monitor actions
+    // above; the frequency and boost flags are accepted but not applied)
+    #[arg(
+        long,
+        help = "Watch AC/battery state and auto-apply the configured EPP profile.",
+        group = "epp_action"
+    )]
+    daemon: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "daemon",
+        help = "Path to the daemon config file (default: /etc/eppcli/daemon.conf)."
+    )]
+    config: Option<PathBuf>,
 }
 
 fn main() {
@@ -295,8 +591,13 @@ fn run_app() -> Result<(), Box<dyn std::error::Error>> {
     let matches = command.get_matches();
     let cli = Cli::from_arg_matches(&matches)?;
 
-    // Instantiate AmdEppMgr
-    let epp_mgr = AmdEppMgr::new()?;
+    if cli.monitor {
+        return run_monitor(cli.interval);
+    }
+
+    if cli.daemon {
+        return daemon::run(cli.config);
+    }
 
     // Determine the desired action based on provided flags
     let mut profile_to_set: Option<EppValue> = None;
@@ -318,14 +619,50 @@ fn run_app() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
+    let cpus = cli.cpu.map(|list| list.0).unwrap_or_default();
+
+    let has_freq_action =
+        cli.max_freq_pct.is_some() || cli.min_freq_pct.is_some() || cli.boost || cli.no_boost;
+
     // If no arguments were provided, or none of the action flags were set, print help.
-    if let Some(profile) = profile_to_set {
-        epp_mgr.apply_profile(profile.as_str())?;
+    if let Some(profile) = &profile_to_set {
+        EppMgr::new()?.apply_profile(profile.as_str(), &cpus)?;
+    } else if let Some(raw) = cli.raw {
+        EppMgr::new()?.apply_profile(&raw.to_string(), &cpus)?;
     } else if cli.show {
-        epp_mgr.read_profile()?;
-    } else {
+        EppMgr::new()?.read_profile(&cpus)?;
+        if let Ok(freq_mgr) = CpuFreqMgr::new() {
+            println!();
+            freq_mgr.print_status(&cpus)?;
+        }
+    } else if !has_freq_action {
         Cli::command().print_help()?;
     }
 
+    if let Some(pct) = cli.max_freq_pct {
+        CpuFreqMgr::new()?.set_max_freq_pct(pct, &cpus)?;
+    }
+    if let Some(pct) = cli.min_freq_pct {
+        CpuFreqMgr::new()?.set_min_freq_pct(pct, &cpus)?;
+    }
+    if cli.boost {
+        CpuFreqMgr::set_boost(true)?;
+    } else if cli.no_boost {
+        CpuFreqMgr::set_boost(false)?;
+    }
+
     Ok(())
 }
+
+/// Runs the RAPL power monitor, refreshing the package power draw in place
+/// every `interval_secs` seconds until interrupted.
+fn run_monitor(interval_secs: u64) -> Result<(), Box<dyn std::error::Error>> {
+    let monitor = PowerMonitor::new()?;
+    let interval = Duration::from_secs(interval_secs);
+
+    loop {
+        let watts = monitor.sample_watts(interval)?;
+        print!("\rPackage power: {:6.2} W", watts);
+        io::stdout().flush()?;
+    }
+}